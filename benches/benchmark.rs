@@ -26,7 +26,7 @@ fn bench_erasedvec(criterion: &mut Criterion) {
 			vec.push::<i32>(rng.gen());
 		}
 
-		bencher.iter_batched(|| vec.clone(), |mut vec| {
+		bencher.iter_batched(|| vec.clone_typed::<i32>(), |mut vec| {
 			let _v = vec.remove::<i32>(0);
 		}, criterion::BatchSize::LargeInput);
 	});
@@ -40,7 +40,7 @@ fn bench_erasedvec(criterion: &mut Criterion) {
 			vec.push::<i32>(rng.gen());
 		}
 
-		bencher.iter_batched(|| vec.clone(), |mut vec| {
+		bencher.iter_batched(|| vec.clone_typed::<i32>(), |mut vec| {
 			vec.erase(0);
 		}, criterion::BatchSize::LargeInput);
 	});
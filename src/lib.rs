@@ -12,7 +12,27 @@ pub struct ErasedVec<A: Allocator> {
 	ptr: *mut u8,
 	len: usize,
 	cap: usize,
-	allocator: A
+	allocator: A,
+	/// Drop glue for the erased element type, or `None` if `T` doesn't need dropping.
+	drop_fn: Option<unsafe fn(*mut u8)>
+}
+
+unsafe fn drop_in_place_erased<T>(ptr: *mut u8) {
+	std::ptr::drop_in_place(ptr as *mut T);
+}
+
+fn drop_fn_for<T>() -> Option<unsafe fn(*mut u8)> {
+	if std::mem::needs_drop::<T>() {
+		Some(drop_in_place_erased::<T>)
+	} else {
+		None
+	}
+}
+
+/// A dangling pointer that is valid for the given alignment, used as the
+/// "allocation" for zero-sized element types, which are never actually allocated.
+fn dangling(align: usize) -> *mut u8 {
+	align as *mut u8
 }
 
 impl ErasedVec<Global> {
@@ -27,70 +47,108 @@ impl ErasedVec<Global> {
 
 impl<A: Allocator> ErasedVec<A> {
 	pub fn new_in<T: 'static>(allocator: A) -> ErasedVec<A> {
+		let element_size = std::mem::size_of::<T>();
+		let element_align = std::mem::align_of::<T>();
+
+		// Zero-sized types are never allocated, so they have effectively unbounded capacity
+		let (ptr, cap) = if element_size == 0 {
+			(dangling(element_align), usize::MAX)
+		} else {
+			(std::ptr::null_mut(), 0)
+		};
+
 		ErasedVec {
 			element_type: std::any::TypeId::of::<T>(),
-			element_size: std::mem::size_of::<T>(),
-			element_align: std::mem::align_of::<T>(),
-			ptr: std::ptr::null_mut(),
+			element_size,
+			element_align,
+			ptr,
 			len: 0,
-			cap: 0,
-			allocator
+			cap,
+			allocator,
+			drop_fn: drop_fn_for::<T>()
 		}
 	}
 
 	pub fn with_capacity_in<T: 'static>(capacity: usize, allocator: A) -> ErasedVec<A> {
-		let layout = std::alloc::Layout::array::<T>(capacity).unwrap();
-		let mem = allocator.allocate(layout).unwrap();
+		let element_size = std::mem::size_of::<T>();
+		let element_align = std::mem::align_of::<T>();
+
+		let (ptr, cap) = if element_size == 0 {
+			(dangling(element_align), usize::MAX)
+		} else {
+			let layout = std::alloc::Layout::array::<T>(capacity).unwrap();
+			let mem = allocator.allocate(layout).unwrap();
+			(mem.as_mut_ptr(), capacity)
+		};
 
 		ErasedVec {
 			element_type: std::any::TypeId::of::<T>(),
-			element_size: std::mem::size_of::<T>(),
-			element_align: std::mem::align_of::<T>(),
-			ptr: mem.as_mut_ptr(),
+			element_size,
+			element_align,
+			ptr,
 			len: 0,
-			cap: capacity,
-			allocator
+			cap,
+			allocator,
+			drop_fn: drop_fn_for::<T>()
 		}
 	}
 
-	/// Grow the vec into memory double the size
-	pub fn grow(&mut self) {
-		if self.cap == 0 {
-			// If the ErasedVec is empty we allocate space for one element and return
-			let layout = std::alloc::Layout::from_size_align(self.element_size, self.element_align).unwrap();
-			let mem = self.allocator.allocate(layout).unwrap();
-			self.ptr = mem.as_mut_ptr();
-			self.cap = 1;
-		}
+	/// Whether the erased element type is zero-sized, and therefore never allocated.
+	fn is_zst(&self) -> bool {
+		self.element_size == 0
+	}
 
-		let old_layout = std::alloc::Layout::from_size_align(self.cap * self.element_size, self.element_align).unwrap();
-		let new_layout = std::alloc::Layout::from_size_align(2 * self.cap * self.element_size, self.element_align).unwrap();
+	/// Number of elements the vec can hold without reallocating.
+	pub fn capacity(&self) -> usize {
+		self.cap
+	}
 
-		// 1. Allocate new memory
-		let mem = self.allocator.allocate(new_layout).unwrap();
+	/// Reserves capacity for at least `additional` more elements, growing the
+	/// backing allocation amortized (like `Vec::reserve`) if it isn't big enough already.
+	pub fn reserve(&mut self, additional: usize) {
+		self.reserve_to(self.len + additional, true);
+	}
 
-		// 2. Copy the elements into the new array
-		unsafe {
-			std::ptr::copy_nonoverlapping(self.ptr, mem.as_mut_ptr(), old_layout.size());
-		}
+	/// Reserves capacity for exactly `additional` more elements, growing the
+	/// backing allocation to exactly that size (like `Vec::reserve_exact`) if it
+	/// isn't big enough already.
+	pub fn reserve_exact(&mut self, additional: usize) {
+		self.reserve_to(self.len + additional, false);
+	}
 
-		// 3. Deallocate the old array
-		unsafe {
-			self.allocator.deallocate(std::ptr::NonNull::new(self.ptr).unwrap(), old_layout);
+	fn reserve_to(&mut self, required_cap: usize, amortized: bool) {
+		if self.is_zst() || required_cap <= self.cap {
+			return;
 		}
 
-		// 4. Update the struct
+		let new_cap = if amortized {
+			std::cmp::max(self.cap * 2, required_cap)
+		} else {
+			required_cap
+		};
+
+		let new_layout = std::alloc::Layout::from_size_align(new_cap * self.element_size, self.element_align).unwrap();
+
+		let mem = if self.cap == 0 {
+			self.allocator.allocate(new_layout).unwrap()
+		} else {
+			let old_layout = std::alloc::Layout::from_size_align(self.cap * self.element_size, self.element_align).unwrap();
+			let old_ptr = std::ptr::NonNull::new(self.ptr).unwrap();
+
+			unsafe {
+				self.allocator.grow(old_ptr, old_layout, new_layout).unwrap()
+			}
+		};
+
 		self.ptr = mem.as_mut_ptr();
-		self.cap = 2 * self.cap;
+		self.cap = new_cap;
 	}
 
 	pub fn push<T: 'static>(&mut self, value: T) {
 		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
 
-		// 1. Grow if the array is too small
-		if self.len == self.cap {
-			self.grow();
-		}
+		// 1. Reserve space for the new element if the array is too small
+		self.reserve(1);
 
 		// 2. Copy the value to the front of the array
 		unsafe {
@@ -101,13 +159,27 @@ impl<A: Allocator> ErasedVec<A> {
 			);
 		}
 
-		// 3. Increment the length
+		// 3. The bytes are now owned by the vec, so don't run value's destructor here
+		std::mem::forget(value);
+
+		// 4. Increment the length
 		self.len += 1;
 	}
 
+	/// Drops the element stored at `index` in place, without touching `len`.
+	unsafe fn drop_element_at(&self, index: usize) {
+		if let Some(drop_fn) = self.drop_fn {
+			drop_fn(self.ptr.offset(index as isize * self.element_size as isize));
+		}
+	}
+
 	pub fn pop(&mut self) {
 		assert!(self.len > 0, "ErasedVec#pop() must not be called on an empty vector");
 
+		unsafe {
+			self.drop_element_at(self.len - 1);
+		}
+
 		self.len -= 1;
 	}
 
@@ -115,6 +187,10 @@ impl<A: Allocator> ErasedVec<A> {
 		self.len
 	}
 
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
 	pub fn get<T: 'static>(&self, index: usize) -> Option<&T> {
 		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
 
@@ -127,49 +203,265 @@ impl<A: Allocator> ErasedVec<A> {
 		}
 	}
 
-	/// Erases an element from the ErasedVec
-	/// 
-	/// If you want the removed element, use [remove] instead
-	pub fn erase(&mut self, index: usize) {
+	pub fn get_mut<T: 'static>(&mut self, index: usize) -> Option<&mut T> {
+		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
+
+		if index >= self.len {
+			None
+		} else {
+			unsafe {
+				Some(&mut *(self.ptr.offset(index as isize * self.element_size as isize) as *mut T))
+			}
+		}
+	}
+
+	/// Views the stored elements as a typed slice.
+	pub fn as_slice<T: 'static>(&self) -> &[T] {
+		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
+
+		// `from_raw_parts` requires a non-null, aligned pointer even for len == 0,
+		// but `ptr` is still null until the first allocation happens
+		let ptr = if self.len == 0 { std::ptr::NonNull::<T>::dangling().as_ptr() } else { self.ptr as *mut T };
+
+		unsafe {
+			std::slice::from_raw_parts(ptr, self.len)
+		}
+	}
+
+	/// Views the stored elements as a mutable typed slice.
+	pub fn as_mut_slice<T: 'static>(&mut self) -> &mut [T] {
+		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
+
+		// `from_raw_parts_mut` requires a non-null, aligned pointer even for len == 0,
+		// but `ptr` is still null until the first allocation happens
+		let ptr = if self.len == 0 { std::ptr::NonNull::<T>::dangling().as_ptr() } else { self.ptr as *mut T };
+
+		unsafe {
+			std::slice::from_raw_parts_mut(ptr, self.len)
+		}
+	}
+
+	/// Borrows `self` as a read-only [Typed] view, giving `Index` access to the
+	/// elements as `T` without going through [get] for every access.
+	pub fn as_typed<T: 'static>(&self) -> Typed<'_, A, T> {
+		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
+
+		Typed {
+			vec: self,
+			_marker: PhantomData
+		}
+	}
+
+	/// Mutably borrows `self` as a [TypedMut] view, giving `Index`/`IndexMut` access
+	/// to the elements as `T` without going through [get]/[get_mut] for every access.
+	pub fn as_typed_mut<T: 'static>(&mut self) -> TypedMut<'_, A, T> {
+		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
+
+		TypedMut {
+			vec: self,
+			_marker: PhantomData
+		}
+	}
+
+	/// Shifts the elements after `index` down by one slot and decrements `len`,
+	/// without dropping whatever currently sits at `index` (the caller must
+	/// have already disposed of it, by dropping it in place or by moving it out).
+	unsafe fn shift_down(&mut self, index: usize) {
 		if index < self.len - 1 {
-		    unsafe {
-		    	std::ptr::copy(self.ptr.offset((index as isize + 1) * self.element_size as isize), self.ptr.offset(index as isize * self.element_size as isize), self.len - 1 - index)
-		    }
+			std::ptr::copy(self.ptr.offset((index as isize + 1) * self.element_size as isize), self.ptr.offset(index as isize * self.element_size as isize), (self.len - 1 - index) * self.element_size)
 		}
 
 		self.len -= 1;
 	}
 
+	/// Erases an element from the ErasedVec
+	///
+	/// If you want the removed element, use [remove] instead
+	pub fn erase(&mut self, index: usize) {
+		unsafe {
+			self.drop_element_at(index);
+			self.shift_down(index);
+		}
+	}
+
 	/// Removes an element from the ErasedVec
-	/// 
+	///
 	/// If you don't need the removed element, use [erase] instead
 	pub fn remove<T: 'static>(&mut self, index: usize) -> T {
 		assert!(index < self.len);
 
-		let val = unsafe {
-			//std::mem::transmute_copy(&(self.ptr.offset(index as isize * self.element_size as isize) as *const T))
-			std::ptr::read(self.ptr.offset(index as isize * self.element_size as isize) as *const T)
-		};
+		unsafe {
+			let val = std::ptr::read(self.ptr.offset(index as isize * self.element_size as isize) as *const T);
 
-		self.erase(index);
+			// The element was moved out above, so shift the tail down without re-dropping it
+			self.shift_down(index);
 
-		val
+			val
+		}
+	}
+
+	/// Inserts `value` at `index`, shifting every element after it one slot to the right.
+	pub fn insert<T: 'static>(&mut self, index: usize, value: T) {
+		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
+		assert!(index <= self.len);
+
+		self.reserve(1);
+
+		unsafe {
+			if index < self.len {
+				std::ptr::copy(
+					self.ptr.offset(index as isize * self.element_size as isize),
+					self.ptr.offset((index as isize + 1) * self.element_size as isize),
+					(self.len - index) * self.element_size
+				);
+			}
+
+			std::ptr::copy_nonoverlapping(
+				&value as *const T as *const u8,
+				self.ptr.offset(index as isize * self.element_size as isize),
+				self.element_size
+			);
+		}
+
+		// The bytes are now owned by the vec, so don't run value's destructor here
+		std::mem::forget(value);
+
+		self.len += 1;
+	}
+
+	/// Removes the element at `index`, moving the last element into its place.
+	///
+	/// This is O(1) but does not preserve ordering, unlike [remove].
+	pub fn swap_remove<T: 'static>(&mut self, index: usize) -> T {
+		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
+		assert!(index < self.len);
+
+		let last = self.len - 1;
+
+		unsafe {
+			let val = std::ptr::read(self.ptr.offset(index as isize * self.element_size as isize) as *const T);
+
+			if index != last {
+				std::ptr::copy_nonoverlapping(
+					self.ptr.offset(last as isize * self.element_size as isize),
+					self.ptr.offset(index as isize * self.element_size as isize),
+					self.element_size
+				);
+			}
+
+			self.len = last;
+
+			val
+		}
 	}
 
-	pub fn into_vec<T: 'static>(&self) -> Vec<T> {
+	/// Moves every element out of `other` and appends it to the end of `self`,
+	/// leaving `other` empty.
+	pub fn append(&mut self, other: &mut ErasedVec<A>) {
+		assert_eq!(self.element_type, other.element_type);
+
+		if other.len == 0 {
+			return;
+		}
+
+		self.reserve(other.len);
+
+		unsafe {
+			std::ptr::copy_nonoverlapping(
+				other.ptr,
+				self.ptr.offset(self.len as isize * self.element_size as isize),
+				other.len * other.element_size
+			);
+		}
+
+		self.len += other.len;
+		other.len = 0;
+	}
+
+	/// Moves all elements out of the ErasedVec and into a `Vec<T>`, consuming it.
+	pub fn into_vec<T: 'static>(mut self) -> Vec<T> {
 		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
 
 		let mut vec = Vec::<T>::with_capacity(self.len);
 
 		if self.len > 0 {
-		    // Copy the data into the vec
+		    // Move the data into the vec
 		    unsafe {
 		    	std::ptr::copy_nonoverlapping(self.ptr, vec.as_mut_ptr() as *mut u8, self.len * self.element_size);
+		    	vec.set_len(self.len);
 		    }
-		}	
+		}
+
+		// The elements now belong to `vec`, so don't run their destructors when
+		// `self` is dropped below (the backing buffer is still deallocated as usual)
+		self.len = 0;
 
 		vec
 	}
+
+	/// Removes the elements in `range` from the vec and returns an iterator that
+	/// yields them by value, matching std `Vec::drain`.
+	///
+	/// If the returned [Drain] is dropped before being fully consumed, the
+	/// remaining elements in the range are dropped in place, and the tail of
+	/// the vec is shifted down to close the gap - elements are never leaked
+	/// or dropped twice.
+	pub fn drain<T: 'static>(&mut self, range: impl std::ops::RangeBounds<usize>) -> Drain<'_, A, T> {
+		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
+
+		let len = self.len;
+
+		let start = match range.start_bound() {
+			std::ops::Bound::Included(&n) => n,
+			std::ops::Bound::Excluded(&n) => n + 1,
+			std::ops::Bound::Unbounded => 0
+		};
+
+		let end = match range.end_bound() {
+			std::ops::Bound::Included(&n) => n + 1,
+			std::ops::Bound::Excluded(&n) => n,
+			std::ops::Bound::Unbounded => len
+		};
+
+		assert!(start <= end && end <= len, "drain range out of bounds");
+
+		// Shrink len to the start of the drained range up front, so that if
+		// `Drain` is leaked (e.g. via mem::forget), the un-drained elements are
+		// simply never seen again instead of being dropped or exposed twice.
+		self.len = start;
+
+		Drain {
+			vec: self,
+			start,
+			idx: start,
+			end,
+			orig_len: len,
+			_marker: PhantomData
+		}
+	}
+
+	/// Consumes the vec and returns an iterator that yields its elements by value.
+	///
+	/// This isn't `IntoIterator::into_iter` since that trait can't express the
+	/// required `T: 'static` turbofish; it's an inherent method instead, like [iter].
+	#[allow(clippy::should_implement_trait)]
+	pub fn into_iter<T: 'static>(self) -> OwnedIter<A, T> {
+		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
+
+		let this = std::mem::ManuallyDrop::new(self);
+
+		OwnedIter {
+			ptr: this.ptr,
+			idx: 0,
+			len: this.len,
+			cap: this.cap,
+			element_size: this.element_size,
+			element_align: this.element_align,
+			// SAFETY: `this` is never used again, so this doesn't leave behind a dangling value
+			allocator: unsafe { std::ptr::read(&this.allocator) },
+			_marker: PhantomData
+		}
+	}
 }
 
 impl<'a, A: Allocator> ErasedVec<A> {
@@ -180,11 +472,21 @@ impl<'a, A: Allocator> ErasedVec<A> {
 			_marker: PhantomData
 		}
 	}
+
+	pub fn iter_mut<T: 'static>(&'a mut self) -> std::slice::IterMut<'a, T> {
+		self.as_mut_slice::<T>().iter_mut()
+	}
 }
 
 impl<A: Allocator> Drop for ErasedVec<A> {
 	fn drop(&mut self) {
-		if self.cap > 0 {
+		unsafe {
+			for i in 0..self.len {
+				self.drop_element_at(i);
+			}
+		}
+
+		if self.cap > 0 && !self.is_zst() {
 			unsafe {
 		    	self.allocator.deallocate(
 		    		std::ptr::NonNull::new(self.ptr).unwrap(),
@@ -195,23 +497,24 @@ impl<A: Allocator> Drop for ErasedVec<A> {
 	}
 }
 
-impl<A: Allocator + Clone> Clone for ErasedVec<A> {
-	fn clone(&self) -> Self {
-		let mem = self.allocator.allocate(std::alloc::Layout::from_size_align(self.cap * self.element_size, self.element_align).unwrap()).unwrap();
+impl<A: Allocator> ErasedVec<A> {
+	/// Clones this vec element-wise using `T::clone`.
+	///
+	/// There's no blanket `Clone` impl because a bytewise copy of the backing
+	/// buffer would be unsound for any `T` owning heap memory: both vecs would
+	/// end up holding the same pointers, and dropping both double-frees. Since
+	/// `ErasedVec` doesn't track `T` itself, the caller has to name it here, the
+	/// same way it does for [get]/[push]/[remove] etc.
+	pub fn clone_typed<T: 'static + Clone>(&self) -> ErasedVec<A> where A: Clone {
+		assert_eq!(std::any::TypeId::of::<T>(), self.element_type);
 
-		unsafe {
-			std::ptr::copy_nonoverlapping(self.ptr, mem.as_mut_ptr(), self.len * self.element_size);
-		}
+		let mut cloned = ErasedVec::with_capacity_in::<T>(self.len, self.allocator.clone());
 
-		ErasedVec {
-			element_type: self.element_type,
-			element_size: self.element_size,
-			element_align: self.element_align,
-			ptr: mem.as_mut_ptr(),
-			len: self.len,
-			cap: self.cap,
-			allocator: self.allocator.clone()
+		for i in 0..self.len {
+			cloned.push(self.get::<T>(i).unwrap().clone());
 		}
+
+		cloned
 	}
 }
 
@@ -231,6 +534,149 @@ impl<'a, A: Allocator + 'static, T: 'static> Iterator for IntoIter<'a, A, T> {
 	}
 }
 
+/// A typed, read-only view over an [ErasedVec], returned by [ErasedVec::as_typed].
+pub struct Typed<'a, A: Allocator, T> {
+	vec: &'a ErasedVec<A>,
+	_marker: PhantomData<T>
+}
+
+impl<'a, A: Allocator, T: 'static> std::ops::Index<usize> for Typed<'a, A, T> {
+	type Output = T;
+
+	fn index(&self, index: usize) -> &T {
+		self.vec.get::<T>(index).expect("index out of bounds")
+	}
+}
+
+/// A typed, mutable view over an [ErasedVec], returned by [ErasedVec::as_typed_mut].
+pub struct TypedMut<'a, A: Allocator, T> {
+	vec: &'a mut ErasedVec<A>,
+	_marker: PhantomData<T>
+}
+
+impl<'a, A: Allocator, T: 'static> std::ops::Index<usize> for TypedMut<'a, A, T> {
+	type Output = T;
+
+	fn index(&self, index: usize) -> &T {
+		self.vec.get::<T>(index).expect("index out of bounds")
+	}
+}
+
+impl<'a, A: Allocator, T: 'static> std::ops::IndexMut<usize> for TypedMut<'a, A, T> {
+	fn index_mut(&mut self, index: usize) -> &mut T {
+		self.vec.get_mut::<T>(index).expect("index out of bounds")
+	}
+}
+
+/// A draining iterator over a range of an [ErasedVec], returned by [ErasedVec::drain].
+pub struct Drain<'a, A: Allocator, T> {
+	vec: &'a mut ErasedVec<A>,
+	/// First index of the drained range (fixed).
+	start: usize,
+	/// Index of the next element to yield.
+	idx: usize,
+	/// One past the last index of the drained range (fixed).
+	end: usize,
+	/// `vec.len()` before the drain started.
+	orig_len: usize,
+	_marker: PhantomData<T>
+}
+
+impl<'a, A: Allocator, T: 'static> Iterator for Drain<'a, A, T> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<T> {
+		if self.idx >= self.end {
+			return None;
+		}
+
+		let val = unsafe {
+			std::ptr::read(self.vec.ptr.offset(self.idx as isize * self.vec.element_size as isize) as *const T)
+		};
+
+		self.idx += 1;
+
+		Some(val)
+	}
+}
+
+impl<'a, A: Allocator, T> Drop for Drain<'a, A, T> {
+	fn drop(&mut self) {
+		// Drop whatever wasn't yielded yet
+		for i in self.idx..self.end {
+			unsafe {
+				std::ptr::drop_in_place(self.vec.ptr.offset(i as isize * self.vec.element_size as isize) as *mut T);
+			}
+		}
+
+		// Shift the tail after the drained range down to close the gap
+		let tail_len = self.orig_len - self.end;
+
+		if tail_len > 0 {
+			unsafe {
+				std::ptr::copy(
+					self.vec.ptr.offset(self.end as isize * self.vec.element_size as isize),
+					self.vec.ptr.offset(self.start as isize * self.vec.element_size as isize),
+					tail_len * self.vec.element_size
+				);
+			}
+		}
+
+		self.vec.len = self.start + tail_len;
+	}
+}
+
+/// An owning iterator over an [ErasedVec], returned by [ErasedVec::into_iter].
+pub struct OwnedIter<A: Allocator, T> {
+	ptr: *mut u8,
+	idx: usize,
+	len: usize,
+	cap: usize,
+	element_size: usize,
+	element_align: usize,
+	allocator: A,
+	_marker: PhantomData<T>
+}
+
+impl<A: Allocator, T: 'static> Iterator for OwnedIter<A, T> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<T> {
+		if self.idx >= self.len {
+			return None;
+		}
+
+		let val = unsafe {
+			std::ptr::read(self.ptr.offset(self.idx as isize * self.element_size as isize) as *const T)
+		};
+
+		self.idx += 1;
+
+		Some(val)
+	}
+}
+
+impl<A: Allocator, T> Drop for OwnedIter<A, T> {
+	fn drop(&mut self) {
+		if std::mem::needs_drop::<T>() {
+			for i in self.idx..self.len {
+				unsafe {
+					std::ptr::drop_in_place(self.ptr.offset(i as isize * self.element_size as isize) as *mut T);
+				}
+			}
+		}
+
+		if self.cap > 0 && self.element_size > 0 {
+			unsafe {
+				self.allocator.deallocate(
+					std::ptr::NonNull::new(self.ptr).unwrap(),
+					std::alloc::Layout::from_size_align(self.cap * self.element_size, self.element_align).unwrap()
+				);
+			}
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -339,6 +785,411 @@ mod tests {
 		vec.push(12i32);
 		vec.push(36i32);
 
-		assert_eq!(vec.iter::<i32>().map(|i| *i).collect::<Vec<_>>(), vec![4, 12, 36]);
+		assert_eq!(vec.iter::<i32>().copied().collect::<Vec<_>>(), vec![4, 12, 36]);
+	}
+
+	struct DropCounter(std::rc::Rc<std::cell::Cell<usize>>);
+
+	impl Drop for DropCounter {
+		fn drop(&mut self) {
+			self.0.set(self.0.get() + 1);
+		}
+	}
+
+	#[test]
+	fn test_drop_on_vec_drop() {
+		let counter = std::rc::Rc::new(std::cell::Cell::new(0));
+
+		let mut vec = ErasedVec::new::<DropCounter>();
+
+		vec.push(DropCounter(counter.clone()));
+		vec.push(DropCounter(counter.clone()));
+		vec.push(DropCounter(counter.clone()));
+
+		drop(vec);
+
+		assert_eq!(counter.get(), 3);
+	}
+
+	#[test]
+	fn test_drop_on_pop() {
+		let counter = std::rc::Rc::new(std::cell::Cell::new(0));
+
+		let mut vec = ErasedVec::new::<DropCounter>();
+
+		vec.push(DropCounter(counter.clone()));
+		vec.push(DropCounter(counter.clone()));
+
+		vec.pop();
+
+		assert_eq!(counter.get(), 1);
+
+		drop(vec);
+
+		assert_eq!(counter.get(), 2);
+	}
+
+	#[test]
+	fn test_drop_on_erase() {
+		let counter = std::rc::Rc::new(std::cell::Cell::new(0));
+
+		let mut vec = ErasedVec::new::<DropCounter>();
+
+		vec.push(DropCounter(counter.clone()));
+		vec.push(DropCounter(counter.clone()));
+
+		vec.erase(0);
+
+		assert_eq!(counter.get(), 1);
+		assert_eq!(vec.len(), 1);
+
+		drop(vec);
+
+		assert_eq!(counter.get(), 2);
+	}
+
+	#[test]
+	fn test_erase_shifts_tail_bytes_for_multi_byte_type() {
+		let mut vec = ErasedVec::new::<u64>();
+
+		vec.push(1u64);
+		vec.push(2u64);
+		vec.push(3u64);
+		vec.push(4u64);
+		vec.push(5u64);
+
+		vec.erase(1);
+
+		assert_eq!(vec.as_slice::<u64>(), &[1, 3, 4, 5]);
+	}
+
+	#[test]
+	fn test_remove_shifts_tail_bytes_for_multi_byte_type() {
+		let mut vec = ErasedVec::new::<u64>();
+
+		vec.push(1u64);
+		vec.push(2u64);
+		vec.push(3u64);
+		vec.push(4u64);
+		vec.push(5u64);
+
+		assert_eq!(vec.remove::<u64>(1), 2);
+		assert_eq!(vec.as_slice::<u64>(), &[1, 3, 4, 5]);
+	}
+
+	#[test]
+	fn test_remove_does_not_double_drop() {
+		let counter = std::rc::Rc::new(std::cell::Cell::new(0));
+
+		let mut vec = ErasedVec::new::<DropCounter>();
+
+		vec.push(DropCounter(counter.clone()));
+
+		let removed = vec.remove::<DropCounter>(0);
+
+		assert_eq!(counter.get(), 0);
+
+		drop(vec);
+
+		assert_eq!(counter.get(), 0);
+
+		drop(removed);
+
+		assert_eq!(counter.get(), 1);
+	}
+
+	#[test]
+	fn test_into_vec_does_not_double_drop() {
+		let counter = std::rc::Rc::new(std::cell::Cell::new(0));
+
+		let mut vec = ErasedVec::new::<DropCounter>();
+
+		vec.push(DropCounter(counter.clone()));
+		vec.push(DropCounter(counter.clone()));
+
+		let plain_vec = vec.into_vec::<DropCounter>();
+
+		assert_eq!(counter.get(), 0);
+
+		drop(plain_vec);
+
+		assert_eq!(counter.get(), 2);
+	}
+
+	#[test]
+	fn test_drain() {
+		let mut vec = ErasedVec::new::<i32>();
+
+		vec.push(1);
+		vec.push(2);
+		vec.push(3);
+		vec.push(4);
+		vec.push(5);
+
+		let drained = vec.drain::<i32>(1..3).collect::<Vec<_>>();
+
+		assert_eq!(drained, vec![2, 3]);
+		assert_eq!(vec.as_slice::<i32>(), &[1, 4, 5]);
+	}
+
+	#[test]
+	fn test_drain_partial_consume_drops_rest() {
+		let counter = std::rc::Rc::new(std::cell::Cell::new(0));
+
+		let mut vec = ErasedVec::new::<DropCounter>();
+
+		vec.push(DropCounter(counter.clone()));
+		vec.push(DropCounter(counter.clone()));
+		vec.push(DropCounter(counter.clone()));
+
+		{
+			let mut drain = vec.drain::<DropCounter>(0..3);
+			drain.next();
+
+			assert_eq!(counter.get(), 1);
+		}
+
+		assert_eq!(counter.get(), 3);
+		assert_eq!(vec.len(), 0);
+	}
+
+	#[test]
+	fn test_into_iter() {
+		let mut vec = ErasedVec::new::<i32>();
+
+		vec.push(1);
+		vec.push(2);
+		vec.push(3);
+
+		assert_eq!(vec.into_iter::<i32>().collect::<Vec<_>>(), vec![1, 2, 3]);
+	}
+
+	#[test]
+	fn test_into_iter_drops_remaining_elements() {
+		let counter = std::rc::Rc::new(std::cell::Cell::new(0));
+
+		let mut vec = ErasedVec::new::<DropCounter>();
+
+		vec.push(DropCounter(counter.clone()));
+		vec.push(DropCounter(counter.clone()));
+
+		{
+			let mut iter = vec.into_iter::<DropCounter>();
+			let first = iter.next();
+
+			assert_eq!(counter.get(), 0);
+
+			drop(first);
+		}
+
+		assert_eq!(counter.get(), 2);
+	}
+
+	#[test]
+	fn test_insert() {
+		let mut vec = ErasedVec::new::<i32>();
+
+		vec.push(1);
+		vec.push(2);
+		vec.push(4);
+
+		vec.insert(2, 3);
+
+		assert_eq!(vec.as_slice::<i32>(), &[1, 2, 3, 4]);
+
+		vec.insert(0, 0);
+
+		assert_eq!(vec.as_slice::<i32>(), &[0, 1, 2, 3, 4]);
+
+		vec.insert(5, 5);
+
+		assert_eq!(vec.as_slice::<i32>(), &[0, 1, 2, 3, 4, 5]);
+	}
+
+	#[test]
+	fn test_swap_remove() {
+		let mut vec = ErasedVec::new::<i32>();
+
+		vec.push(1);
+		vec.push(2);
+		vec.push(3);
+		vec.push(4);
+
+		assert_eq!(vec.swap_remove::<i32>(1), 2);
+		assert_eq!(vec.as_slice::<i32>(), &[1, 4, 3]);
+	}
+
+	#[test]
+	fn test_append() {
+		let mut a = ErasedVec::new::<i32>();
+		a.push(1);
+		a.push(2);
+
+		let mut b = ErasedVec::new::<i32>();
+		b.push(3);
+		b.push(4);
+
+		a.append(&mut b);
+
+		assert_eq!(a.as_slice::<i32>(), &[1, 2, 3, 4]);
+		assert_eq!(b.len(), 0);
+	}
+
+	#[test]
+	fn test_slices() {
+		let mut vec = ErasedVec::with_capacity::<i32>(4);
+
+		vec.push(4i32);
+		vec.push(12i32);
+		vec.push(36i32);
+
+		assert_eq!(vec.as_slice::<i32>(), &[4, 12, 36]);
+
+		for value in vec.as_mut_slice::<i32>() {
+			*value *= 2;
+		}
+
+		assert_eq!(vec.as_slice::<i32>(), &[8, 24, 72]);
+	}
+
+	#[test]
+	fn test_empty_slice_never_allocated() {
+		let mut vec = ErasedVec::new::<i32>();
+
+		assert_eq!(vec.as_slice::<i32>(), &[] as &[i32]);
+		assert_eq!(vec.as_mut_slice::<i32>(), &mut [] as &mut [i32]);
+	}
+
+	#[test]
+	fn test_get_mut_and_iter_mut() {
+		let mut vec = ErasedVec::with_capacity::<i32>(4);
+
+		vec.push(4i32);
+		vec.push(12i32);
+
+		*vec.get_mut::<i32>(0).unwrap() += 1;
+
+		for value in vec.iter_mut::<i32>() {
+			*value *= 10;
+		}
+
+		assert_eq!(vec.get::<i32>(0), Some(&50));
+		assert_eq!(vec.get::<i32>(1), Some(&120));
+	}
+
+	#[test]
+	fn test_typed_index() {
+		let mut vec = ErasedVec::with_capacity::<i32>(4);
+
+		vec.push(4i32);
+		vec.push(12i32);
+
+		assert_eq!(vec.as_typed::<i32>()[0], 4);
+		assert_eq!(vec.as_typed::<i32>()[1], 12);
+
+		vec.as_typed_mut::<i32>()[0] = 100;
+
+		assert_eq!(vec.get::<i32>(0), Some(&100));
+	}
+
+	#[test]
+	fn test_reserve() {
+		let mut vec = ErasedVec::new::<i32>();
+
+		assert_eq!(vec.capacity(), 0);
+
+		vec.reserve_exact(3);
+
+		assert_eq!(vec.capacity(), 3);
+
+		vec.push(1);
+		vec.push(2);
+		vec.push(3);
+
+		assert_eq!(vec.capacity(), 3);
+
+		vec.reserve(1);
+
+		assert!(vec.capacity() > 3);
+		assert_eq!(vec.get::<i32>(0), Some(&1));
+		assert_eq!(vec.get::<i32>(1), Some(&2));
+		assert_eq!(vec.get::<i32>(2), Some(&3));
+	}
+
+	#[test]
+	fn test_zst() {
+		let mut vec = ErasedVec::new::<()>();
+
+		vec.push(());
+		vec.push(());
+		vec.push(());
+
+		assert_eq!(vec.len(), 3);
+		assert_eq!(vec.get::<()>(0), Some(&()));
+		assert_eq!(vec.get::<()>(3), None);
+
+		vec.pop();
+
+		assert_eq!(vec.len(), 2);
+
+		drop(vec);
+	}
+
+	#[test]
+	fn test_zst_drop_glue() {
+		thread_local! {
+			static DROPS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+		}
+
+		struct ZstDropCounter;
+
+		impl Drop for ZstDropCounter {
+			fn drop(&mut self) {
+				DROPS.with(|drops| drops.set(drops.get() + 1));
+			}
+		}
+
+		let mut vec = ErasedVec::new::<ZstDropCounter>();
+
+		vec.push(ZstDropCounter);
+		vec.push(ZstDropCounter);
+
+		drop(vec);
+
+		DROPS.with(|drops| assert_eq!(drops.get(), 2));
+	}
+
+	#[test]
+	fn test_no_leak_of_heap_owning_elements() {
+		let mut vec = ErasedVec::new::<String>();
+
+		vec.push(String::from("hello"));
+		vec.push(String::from("world"));
+
+		assert_eq!(vec.get::<String>(0), Some(&String::from("hello")));
+		assert_eq!(vec.get::<String>(1), Some(&String::from("world")));
+
+		drop(vec);
+	}
+
+	#[test]
+	fn test_clone_typed_of_heap_owning_elements_does_not_double_free() {
+		let mut vec = ErasedVec::new::<String>();
+
+		vec.push(String::from("hello"));
+		vec.push(String::from("world"));
+
+		let cloned = vec.clone_typed::<String>();
+
+		assert_eq!(vec.as_slice::<String>(), cloned.as_slice::<String>());
+
+		// Mutating one shouldn't affect the other - they must not share a buffer
+		vec.get_mut::<String>(0).unwrap().push_str(", world");
+
+		assert_eq!(vec.get::<String>(0), Some(&String::from("hello, world")));
+		assert_eq!(cloned.get::<String>(0), Some(&String::from("hello")));
+
+		drop(vec);
+		drop(cloned);
 	}
 }